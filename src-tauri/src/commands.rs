@@ -0,0 +1,130 @@
+//! `#[tauri::command]`s that keep JSON parsing and querying in Rust, off the
+//! main JS thread, so opening and searching huge documents doesn't freeze the
+//! webview or blow its heap.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_fs::FsExt;
+
+use crate::jsonpath;
+
+/// Checks `path` against the fs scope populated by [`open_file`], so every
+/// command that touches the filesystem is limited to files the user has
+/// actually picked rather than the whole disk.
+pub(crate) fn is_allowed<R: tauri::Runtime>(app: &tauri::AppHandle<R>, path: &Path) -> bool {
+    app.fs_scope().is_allowed(path)
+}
+
+fn require_allowed<R: tauri::Runtime>(app: &tauri::AppHandle<R>, path: &str) -> Result<(), String> {
+    if is_allowed(app, Path::new(path)) {
+        Ok(())
+    } else {
+        Err(format!("'{path}' is outside the granted fs scope"))
+    }
+}
+
+/// Structural summary of a JSON file, returned without the frontend ever
+/// having to load the whole document.
+#[derive(Debug, Serialize)]
+pub struct FileMeta {
+    pub size_bytes: u64,
+    pub kind: &'static str,
+    pub child_count: usize,
+    pub line_count: usize,
+}
+
+/// A single match produced by [`query`], paired with its location.
+#[derive(Debug, Serialize)]
+pub struct QueryMatch {
+    pub pointer: String,
+    pub value: serde_json::Value,
+}
+
+/// Loads `path`, validates it as JSON and returns structural metadata about
+/// it (size, top-level kind, child count, line count) without handing the
+/// parsed document back to the frontend.
+#[tauri::command]
+pub fn load_file(app: tauri::AppHandle, path: String) -> Result<FileMeta, String> {
+    require_allowed(&app, &path)?;
+    let contents = fs::read_to_string(&path).map_err(|e| format!("failed to read {path}: {e}"))?;
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(describe_parse_error)?;
+
+    let (kind, child_count) = match &value {
+        serde_json::Value::Object(map) => ("object", map.len()),
+        serde_json::Value::Array(items) => ("array", items.len()),
+        serde_json::Value::String(_) => ("string", 0),
+        serde_json::Value::Number(_) => ("number", 0),
+        serde_json::Value::Bool(_) => ("bool", 0),
+        serde_json::Value::Null => ("null", 0),
+    };
+
+    Ok(FileMeta {
+        size_bytes: contents.len() as u64,
+        kind,
+        child_count,
+        line_count: contents.lines().count(),
+    })
+}
+
+/// Fetches the subtree at `pointer` (a JSON Pointer, RFC 6901) within `path`,
+/// for lazily expanding a single node in the tree view.
+#[tauri::command]
+pub fn get_subtree(app: tauri::AppHandle, path: String, pointer: String) -> Result<serde_json::Value, String> {
+    require_allowed(&app, &path)?;
+    let contents = fs::read_to_string(&path).map_err(|e| format!("failed to read {path}: {e}"))?;
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(describe_parse_error)?;
+
+    value
+        .pointer(&pointer)
+        .cloned()
+        .ok_or_else(|| format!("no node at pointer '{pointer}'"))
+}
+
+/// Runs a JSONPath `expression` against `path` and returns every matched
+/// value alongside its JSON Pointer location, so the frontend can scroll to
+/// and highlight each match.
+#[tauri::command]
+pub fn query(app: tauri::AppHandle, path: String, expression: String) -> Result<Vec<QueryMatch>, String> {
+    require_allowed(&app, &path)?;
+    let contents = fs::read_to_string(&path).map_err(|e| format!("failed to read {path}: {e}"))?;
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(describe_parse_error)?;
+
+    jsonpath::query(&value, &expression)
+        .map(|matches| {
+            matches
+                .into_iter()
+                .map(|m| QueryMatch { pointer: m.pointer, value: m.value })
+                .collect()
+        })
+        .map_err(|e| format!("invalid JSONPath expression: {e}"))
+}
+
+/// Prompts the user to pick a file via the native dialog and, if one was
+/// chosen, grants it to the fs scope so the webview can read exactly that
+/// path and nothing else. Returns the allowed path, or `None` if the dialog
+/// was cancelled.
+///
+/// Deliberately a sync command, not `async`: Tauri dispatches sync commands
+/// on its blocking thread pool, while an `async` command runs on the async
+/// runtime itself, where `blocking_pick_file` would park a worker thread for
+/// as long as the (user-controlled) dialog stays open.
+#[tauri::command]
+pub fn open_file(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let Some(file_path) = app.dialog().file().blocking_pick_file() else {
+        return Ok(None);
+    };
+
+    let path = file_path.into_path().map_err(|e| e.to_string())?;
+    app.fs_scope()
+        .allow_file(&path)
+        .map_err(|e| format!("failed to grant fs scope for {}: {e}", path.display()))?;
+
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+fn describe_parse_error(e: serde_json::Error) -> String {
+    format!("JSON parse error at line {}, column {}: {}", e.line(), e.column(), e)
+}
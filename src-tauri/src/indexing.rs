@@ -0,0 +1,644 @@
+//! Background indexing subsystem.
+//!
+//! On open, a worker does a single streaming pass over the raw file bytes
+//! and builds a flat index of every node (pointer, byte range, depth, kind,
+//! one-line preview). The frontend then pages through that index with
+//! [`get_rows`] instead of ever materializing the whole tree in JS, and
+//! toggles which branches are expanded with [`set_expanded`].
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::commands;
+
+const PREVIEW_LEN: usize = 80;
+const PROGRESS_INTERVAL: usize = 2000;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexEntry {
+    pub pointer: String,
+    pub byte_start: u64,
+    pub byte_end: u64,
+    pub depth: usize,
+    pub kind: NodeKind,
+    pub preview: String,
+}
+
+/// The full flat index for one open document: every node in pre-order
+/// (`entries[0]` is always the document root), which pointers are currently
+/// expanded, and the flattened visible order kept up to date incrementally
+/// as nodes expand/collapse rather than recomputed on every page fetch.
+#[derive(Default)]
+struct Index {
+    entries: Vec<IndexEntry>,
+    /// Maps a parent pointer to the indices of its direct children in `entries`.
+    children: HashMap<String, Vec<usize>>,
+    expanded: HashSet<String>,
+    /// Indices into `entries`, in the order rows currently render in the tree.
+    visible: Vec<usize>,
+}
+
+/// Documents currently held in memory, keyed by the id handed back from
+/// [`open_document`].
+#[derive(Default)]
+pub struct DocStore {
+    next_id: AtomicU64,
+    docs: Arc<RwLock<HashMap<u64, Index>>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OpenDocumentResponse {
+    pub doc_id: u64,
+    pub total_nodes: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IndexProgress {
+    pub doc_id: u64,
+    pub nodes_indexed: usize,
+    pub bytes_total: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RowView {
+    pub pointer: String,
+    pub depth: usize,
+    pub kind: NodeKind,
+    pub preview: String,
+    pub expanded: bool,
+    pub expandable: bool,
+}
+
+/// Reads `path`, indexes it on a background task and returns the new
+/// document's id once indexing has finished; while the caller awaits,
+/// `index-progress` events report how far the indexing worker has gotten,
+/// and the caller can start calling [`get_rows`] as soon as the returned
+/// future resolves.
+#[tauri::command]
+pub async fn open_document(
+    app: tauri::AppHandle,
+    store: tauri::State<'_, DocStore>,
+    path: String,
+) -> Result<OpenDocumentResponse, String> {
+    if !commands::is_allowed(&app, Path::new(&path)) {
+        return Err(format!("'{path}' is outside the granted fs scope"));
+    }
+
+    let doc_id = store.next_id.fetch_add(1, Ordering::SeqCst);
+    let docs = store.docs.clone();
+
+    let response = tauri::async_runtime::spawn_blocking(move || -> Result<OpenDocumentResponse, String> {
+        let bytes = std::fs::read(&path).map_err(|e| format!("failed to read {path}: {e}"))?;
+        let bytes_total = bytes.len() as u64;
+
+        let mut indexer = Indexer {
+            bytes: &bytes,
+            pos: 0,
+            entries: Vec::new(),
+            children: HashMap::new(),
+            on_progress: |count| {
+                if count % PROGRESS_INTERVAL == 0 {
+                    let _ = app.emit(
+                        "index-progress",
+                        IndexProgress { doc_id, nodes_indexed: count, bytes_total },
+                    );
+                }
+            },
+        };
+        indexer.skip_whitespace();
+        indexer.index_value(String::new(), 0)?;
+
+        let total_nodes = indexer.entries.len();
+        let mut expanded = HashSet::new();
+        expanded.insert(String::new());
+
+        let mut index = Index { entries: indexer.entries, children: indexer.children, expanded, visible: Vec::new() };
+        index.visible = initial_visible(&index);
+
+        let mut docs = docs.write().map_err(|_| "index lock poisoned".to_string())?;
+        docs.insert(doc_id, index);
+
+        let _ = app.emit("index-progress", IndexProgress { doc_id, nodes_indexed: total_nodes, bytes_total });
+
+        Ok(OpenDocumentResponse { doc_id, total_nodes })
+    })
+    .await
+    .map_err(|e| format!("indexing task failed: {e}"))??;
+
+    Ok(response)
+}
+
+/// Returns `count` contiguous rows of the currently-visible flattened tree
+/// (i.e. respecting which nodes are expanded), starting at `start`. Reads
+/// straight off the maintained `visible` order, so cost is proportional to
+/// the page requested, not to the size of the document.
+#[tauri::command]
+pub fn get_rows(store: tauri::State<'_, DocStore>, doc_id: u64, start: usize, count: usize) -> Result<Vec<RowView>, String> {
+    let docs = store.docs.read().map_err(|_| "index lock poisoned".to_string())?;
+    let index = docs.get(&doc_id).ok_or_else(|| format!("no document with id {doc_id}"))?;
+
+    Ok(index
+        .visible
+        .iter()
+        .skip(start)
+        .take(count)
+        .map(|&i| {
+            let entry = &index.entries[i];
+            RowView {
+                pointer: entry.pointer.clone(),
+                depth: entry.depth,
+                kind: entry.kind,
+                preview: entry.preview.clone(),
+                expanded: index.expanded.contains(&entry.pointer),
+                expandable: index.children.contains_key(&entry.pointer),
+            }
+        })
+        .collect())
+}
+
+/// Toggles whether `pointer` is expanded in `doc_id`'s tree, changing which
+/// rows [`get_rows`] will return as visible.
+#[tauri::command]
+pub fn set_expanded(store: tauri::State<'_, DocStore>, doc_id: u64, pointer: String, expanded: bool) -> Result<(), String> {
+    let mut docs = store.docs.write().map_err(|_| "index lock poisoned".to_string())?;
+    let index = docs.get_mut(&doc_id).ok_or_else(|| format!("no document with id {doc_id}"))?;
+    toggle_expanded(index, pointer, expanded);
+    Ok(())
+}
+
+/// Evicts `doc_id`'s flat index from memory. The frontend calls this when a
+/// document's tab/view closes, since nothing else ever shrinks `DocStore` and
+/// each open document's index is sized to the whole file's node count.
+#[tauri::command]
+pub fn close_document(store: tauri::State<'_, DocStore>, doc_id: u64) -> Result<(), String> {
+    let mut docs = store.docs.write().map_err(|_| "index lock poisoned".to_string())?;
+    docs.remove(&doc_id);
+    Ok(())
+}
+
+/// Applies an expand/collapse toggle, patching `index.visible` in place
+/// instead of rebuilding it: expanding splices in just the newly-revealed
+/// subtree at the node's current position, collapsing drains the
+/// contiguous run of its descendants.
+fn toggle_expanded(index: &mut Index, pointer: String, expanded: bool) {
+    if expanded {
+        if !index.expanded.insert(pointer.clone()) {
+            return;
+        }
+        let Some(pos) = index.visible.iter().position(|&i| index.entries[i].pointer == pointer) else {
+            return;
+        };
+        let mut revealed = Vec::new();
+        collect_subtree_visible(index, &pointer, &mut revealed);
+        index.visible.splice(pos + 1..pos + 1, revealed);
+    } else {
+        if !index.expanded.remove(&pointer) {
+            return;
+        }
+        let Some(pos) = index.visible.iter().position(|&i| index.entries[i].pointer == pointer) else {
+            return;
+        };
+        let hidden = index.visible[pos + 1..]
+            .iter()
+            .take_while(|&&i| is_descendant(&pointer, &index.entries[i].pointer))
+            .count();
+        index.visible.drain(pos + 1..pos + 1 + hidden);
+    }
+}
+
+/// Whether `candidate` is a (possibly indirect) child pointer of `ancestor`.
+fn is_descendant(ancestor: &str, candidate: &str) -> bool {
+    candidate.len() > ancestor.len()
+        && candidate.starts_with(ancestor)
+        && candidate.as_bytes()[ancestor.len()] == b'/'
+}
+
+/// Builds the initial visible order for a freshly-indexed document: the
+/// root, plus the subtree of whatever is expanded by default.
+fn initial_visible(index: &Index) -> Vec<usize> {
+    let mut rows = Vec::new();
+    if index.entries.is_empty() {
+        return rows;
+    }
+    rows.push(0);
+    if index.expanded.contains(&index.entries[0].pointer) {
+        collect_subtree_visible(index, &index.entries[0].pointer, &mut rows);
+    }
+    rows
+}
+
+/// Depth-first walk of `pointer`'s children, descending into any child that
+/// is itself expanded.
+fn collect_subtree_visible(index: &Index, pointer: &str, rows: &mut Vec<usize>) {
+    let Some(children) = index.children.get(pointer) else { return };
+    for &child_idx in children {
+        rows.push(child_idx);
+        let child_pointer = &index.entries[child_idx].pointer;
+        if index.expanded.contains(child_pointer) {
+            collect_subtree_visible(index, child_pointer, rows);
+        }
+    }
+}
+
+/// Hand-rolled single-pass JSON scanner. Unlike `serde_json::Value`, it
+/// never builds a generic tree — each node is turned directly into an
+/// [`IndexEntry`] and discarded, so the only memory it adds on top of the
+/// raw bytes it scans is the index itself. Entries are pushed in pre-order (a container's own
+/// entry before any of its children's) so `entries[0]` is always the root.
+struct Indexer<'a, F: FnMut(usize)> {
+    bytes: &'a [u8],
+    pos: usize,
+    entries: Vec<IndexEntry>,
+    children: HashMap<String, Vec<usize>>,
+    on_progress: F,
+}
+
+impl<'a, F: FnMut(usize)> Indexer<'a, F> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.bytes.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn index_value(&mut self, pointer: String, depth: usize) -> Result<usize, String> {
+        self.skip_whitespace();
+        let start = self.pos as u64;
+
+        let entry_idx = match self.bytes.get(self.pos) {
+            Some(b'{') => self.index_object(pointer.clone(), depth, start)?,
+            Some(b'[') => self.index_array(pointer.clone(), depth, start)?,
+            Some(b'"') => self.index_scalar(pointer, depth, start, NodeKind::String)?,
+            Some(b't') | Some(b'f') => self.index_scalar(pointer, depth, start, NodeKind::Bool)?,
+            Some(b'n') => self.index_scalar(pointer, depth, start, NodeKind::Null)?,
+            Some(_) => self.index_scalar(pointer, depth, start, NodeKind::Number)?,
+            None => return Err("unexpected end of input".to_string()),
+        };
+
+        (self.on_progress)(self.entries.len());
+        Ok(entry_idx)
+    }
+
+    fn index_object(&mut self, pointer: String, depth: usize, start: u64) -> Result<usize, String> {
+        let entry_idx = self.push_placeholder(pointer.clone(), start, depth, NodeKind::Object);
+        self.pos += 1; // consume '{'
+        let mut count = 0usize;
+        let mut child_indices = Vec::new();
+
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.bytes.get(self.pos) != Some(&b'"') {
+                    return Err(format!("expected '\"' at byte {}", self.pos));
+                }
+                let key_start = self.pos;
+                self.skip_string()?;
+                let key = unescape(&self.bytes[key_start + 1..self.pos - 1]);
+                self.skip_whitespace();
+                if self.bytes.get(self.pos) != Some(&b':') {
+                    return Err(format!("expected ':' at byte {}", self.pos));
+                }
+                self.pos += 1;
+
+                let child_pointer = format!("{pointer}/{}", escape_pointer(&key));
+                let child_idx = self.index_value(child_pointer, depth + 1)?;
+                child_indices.push(child_idx);
+                count += 1;
+
+                self.skip_whitespace();
+                match self.bytes.get(self.pos) {
+                    Some(b',') => self.pos += 1,
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(format!("expected ',' or '}}' at byte {}", self.pos)),
+                }
+            }
+        }
+
+        let preview = format!("{{ {count} {} }}", if count == 1 { "key" } else { "keys" });
+        self.entries[entry_idx].preview = preview;
+        self.entries[entry_idx].byte_end = self.pos as u64;
+        self.children.insert(pointer, child_indices);
+        Ok(entry_idx)
+    }
+
+    fn index_array(&mut self, pointer: String, depth: usize, start: u64) -> Result<usize, String> {
+        let entry_idx = self.push_placeholder(pointer.clone(), start, depth, NodeKind::Array);
+        self.pos += 1; // consume '['
+        let mut count = 0usize;
+        let mut child_indices = Vec::new();
+
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                let child_pointer = format!("{pointer}/{count}");
+                let child_idx = self.index_value(child_pointer, depth + 1)?;
+                child_indices.push(child_idx);
+                count += 1;
+
+                self.skip_whitespace();
+                match self.bytes.get(self.pos) {
+                    Some(b',') => self.pos += 1,
+                    Some(b']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(format!("expected ',' or ']' at byte {}", self.pos)),
+                }
+            }
+        }
+
+        let preview = format!("[ {count} {} ]", if count == 1 { "item" } else { "items" });
+        self.entries[entry_idx].preview = preview;
+        self.entries[entry_idx].byte_end = self.pos as u64;
+        self.children.insert(pointer, child_indices);
+        Ok(entry_idx)
+    }
+
+    fn index_scalar(&mut self, pointer: String, depth: usize, start: u64, kind: NodeKind) -> Result<usize, String> {
+        match kind {
+            NodeKind::String => self.skip_string()?,
+            _ => {
+                while matches!(
+                    self.bytes.get(self.pos),
+                    Some(c) if !matches!(c, b',' | b']' | b'}' | b':' | b'"' | b'[' | b'{' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+            }
+        }
+
+        let raw = &self.bytes[start as usize..self.pos];
+        let preview = truncate_preview(&String::from_utf8_lossy(raw));
+        Ok(self.push_entry(pointer, start, depth, kind, preview))
+    }
+
+    fn skip_string(&mut self) -> Result<(), String> {
+        self.pos += 1; // opening quote
+        loop {
+            match self.bytes.get(self.pos) {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(_) => self.pos += 1,
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    /// Pushes a finished leaf entry, with `byte_end` at the current cursor.
+    fn push_entry(&mut self, pointer: String, start: u64, depth: usize, kind: NodeKind, preview: String) -> usize {
+        let entry = IndexEntry { pointer, byte_start: start, byte_end: self.pos as u64, depth, kind, preview };
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+
+    /// Pushes a container's entry before its children are parsed, so it
+    /// lands before them in pre-order; `preview`/`byte_end` are backfilled
+    /// once the container is fully parsed.
+    fn push_placeholder(&mut self, pointer: String, start: u64, depth: usize, kind: NodeKind) -> usize {
+        let entry = IndexEntry { pointer, byte_start: start, byte_end: start, depth, kind, preview: String::new() };
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+}
+
+fn truncate_preview(s: &str) -> String {
+    if s.chars().count() <= PREVIEW_LEN {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(PREVIEW_LEN).collect();
+        format!("{truncated}…")
+    }
+}
+
+/// Decodes the standard JSON string escapes (`\"`, `\\`, `\/`, `\b`, `\f`,
+/// `\n`, `\r`, `\t`, `\uXXXX` including surrogate pairs) so the resulting
+/// key matches exactly what `serde_json` would produce for the same source
+/// text — required for the pointers we hand out to resolve via
+/// `Value::pointer` in `commands::get_subtree`/`query`.
+fn unescape(raw: &[u8]) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut i = 0;
+
+    while i < raw.len() {
+        if raw[i] != b'\\' {
+            let start = i;
+            while i < raw.len() && raw[i] != b'\\' {
+                i += 1;
+            }
+            out.push_str(&String::from_utf8_lossy(&raw[start..i]));
+            continue;
+        }
+
+        match raw.get(i + 1) {
+            Some(b'"') => {
+                out.push('"');
+                i += 2;
+            }
+            Some(b'\\') => {
+                out.push('\\');
+                i += 2;
+            }
+            Some(b'/') => {
+                out.push('/');
+                i += 2;
+            }
+            Some(b'b') => {
+                out.push('\u{8}');
+                i += 2;
+            }
+            Some(b'f') => {
+                out.push('\u{c}');
+                i += 2;
+            }
+            Some(b'n') => {
+                out.push('\n');
+                i += 2;
+            }
+            Some(b'r') => {
+                out.push('\r');
+                i += 2;
+            }
+            Some(b't') => {
+                out.push('\t');
+                i += 2;
+            }
+            Some(b'u') => match read_hex4(raw, i + 2) {
+                Some(code) if (0xD800..=0xDBFF).contains(&code) => {
+                    let low = (raw.get(i + 6) == Some(&b'\\') && raw.get(i + 7) == Some(&b'u'))
+                        .then(|| read_hex4(raw, i + 8))
+                        .flatten()
+                        .filter(|low| (0xDC00..=0xDFFF).contains(low));
+
+                    match low {
+                        Some(low) => {
+                            let combined = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            out.push(char::from_u32(combined).unwrap_or('\u{FFFD}'));
+                            i += 12;
+                        }
+                        None => {
+                            out.push('\u{FFFD}');
+                            i += 6;
+                        }
+                    }
+                }
+                Some(code) => {
+                    out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    i += 6;
+                }
+                None => {
+                    out.push('\\');
+                    i += 1;
+                }
+            },
+            _ => {
+                out.push('\\');
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Reads the 4 hex digits of a `\uXXXX` escape starting at `pos` (just past
+/// the `u`).
+fn read_hex4(raw: &[u8], pos: usize) -> Option<u32> {
+    let slice = raw.get(pos..pos + 4)?;
+    let s = std::str::from_utf8(slice).ok()?;
+    u32::from_str_radix(s, 16).ok()
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_bytes(json: &str) -> Result<(Vec<IndexEntry>, HashMap<String, Vec<usize>>), String> {
+        let bytes = json.as_bytes();
+        let mut indexer = Indexer { bytes, pos: 0, entries: Vec::new(), children: HashMap::new(), on_progress: |_| {} };
+        indexer.skip_whitespace();
+        indexer.index_value(String::new(), 0)?;
+        Ok((indexer.entries, indexer.children))
+    }
+
+    #[test]
+    fn root_is_always_the_first_entry() {
+        let (entries, _) = index_bytes(r#"{"a": {"b": 1}}"#).unwrap();
+        assert_eq!(entries[0].pointer, "");
+        assert_eq!(entries[0].kind as u8, NodeKind::Object as u8);
+    }
+
+    #[test]
+    fn indexes_flat_object() {
+        let (entries, children) = index_bytes(r#"{"a": 1, "b": "two"}"#).unwrap();
+        assert_eq!(entries[0].kind as u8, NodeKind::Object as u8);
+        assert_eq!(entries[0].preview, "{ 2 keys }");
+        assert_eq!(children.get("").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn indexes_nested_array() {
+        let (entries, _) = index_bytes(r#"[1, [2, 3]]"#).unwrap();
+        let nested = entries.iter().find(|e| e.pointer == "/1").unwrap();
+        assert_eq!(nested.preview, "[ 2 items ]");
+    }
+
+    #[test]
+    fn byte_ranges_cover_the_source_slice() {
+        let json = r#"{"k": 42}"#;
+        let (entries, _) = index_bytes(json).unwrap();
+        let value = entries.iter().find(|e| e.pointer == "/k").unwrap();
+        assert_eq!(&json[value.byte_start as usize..value.byte_end as usize], "42");
+    }
+
+    #[test]
+    fn rejects_object_missing_comma() {
+        let err = index_bytes(r#"{"a":1"b":2}"#).unwrap_err();
+        assert!(err.contains("expected ',' or '}'"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn rejects_array_missing_comma() {
+        let err = index_bytes(r#"[1 2]"#).unwrap_err();
+        assert!(err.contains("expected ',' or ']'"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn escaped_keys_produce_pointers_serde_json_resolves() {
+        let json = r#"{"a\nb": 1, "snowman\u2603": 2}"#;
+        let (entries, _) = index_bytes(json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        let child_pointers: Vec<&str> =
+            entries.iter().filter(|e| e.depth == 1).map(|e| e.pointer.as_str()).collect();
+        assert_eq!(child_pointers.len(), 2);
+        for pointer in child_pointers {
+            assert!(value.pointer(pointer).is_some(), "pointer '{pointer}' should resolve via serde_json, matching commands::get_subtree");
+        }
+    }
+
+    fn build_index(json: &str) -> Index {
+        let (entries, children) = index_bytes(json).unwrap();
+        let mut expanded = HashSet::new();
+        expanded.insert(String::new());
+        let mut index = Index { entries, children, expanded, visible: Vec::new() };
+        index.visible = initial_visible(&index);
+        index
+    }
+
+    fn visible_pointers(index: &Index) -> Vec<&str> {
+        index.visible.iter().map(|&i| index.entries[i].pointer.as_str()).collect()
+    }
+
+    #[test]
+    fn visible_rows_respect_expansion() {
+        let index = build_index(r#"{"a": {"b": 1}}"#);
+        assert_eq!(visible_pointers(&index), vec!["", "/a"]);
+    }
+
+    #[test]
+    fn expanding_splices_in_the_revealed_subtree() {
+        let mut index = build_index(r#"{"a": {"b": 1, "c": 2}}"#);
+        toggle_expanded(&mut index, "/a".to_string(), true);
+        assert_eq!(visible_pointers(&index), vec!["", "/a", "/a/b", "/a/c"]);
+    }
+
+    #[test]
+    fn collapsing_drains_only_the_hidden_descendants() {
+        let mut index = build_index(r#"{"a": {"b": 1}, "z": 9}"#);
+        toggle_expanded(&mut index, "/a".to_string(), true);
+        assert_eq!(visible_pointers(&index), vec!["", "/a", "/a/b", "/z"]);
+
+        toggle_expanded(&mut index, "/a".to_string(), false);
+        assert_eq!(visible_pointers(&index), vec!["", "/a", "/z"]);
+    }
+}
@@ -0,0 +1,443 @@
+//! A small JSONPath evaluator used by the `query` command.
+//!
+//! Supports the subset of JSONPath needed to navigate and filter arbitrary
+//! JSON documents: dot and bracket child access, the `*` wildcard, `..`
+//! recursive descent, `[start:end:step]` array slices and `[?(@.expr)]`
+//! filter expressions with a single comparison against a literal.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(String),
+    Index(i64),
+    Wildcard,
+    RecursiveDescent,
+    Slice(Option<i64>, Option<i64>, i64),
+    Filter(FilterExpr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FilterExpr {
+    field: String,
+    op: CompareOp,
+    value: Literal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Null,
+}
+
+/// One matched node: its value and its location as a JSON Pointer.
+pub struct Match {
+    pub value: Value,
+    pub pointer: String,
+}
+
+/// Parses and evaluates `expression` against `root`, returning every
+/// matching node along with its JSON Pointer location.
+pub fn query(root: &Value, expression: &str) -> Result<Vec<Match>, String> {
+    let segments = parse(expression)?;
+    let mut matches = vec![(String::new(), root.clone())];
+
+    for segment in &segments {
+        let mut next = Vec::new();
+        for (pointer, value) in matches {
+            apply(segment, &pointer, &value, &mut next);
+        }
+        matches = next;
+    }
+
+    Ok(matches
+        .into_iter()
+        .map(|(pointer, value)| Match { value, pointer })
+        .collect())
+}
+
+fn apply(segment: &Segment, pointer: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+    match segment {
+        Segment::Child(name) => {
+            if let Some(v) = value.get(name) {
+                out.push((format!("{pointer}/{}", escape_pointer(name)), v.clone()));
+            }
+        }
+        Segment::Index(i) => {
+            if let Some(v) = index_array(value, *i) {
+                let idx = normalize_index(value, *i);
+                out.push((format!("{pointer}/{idx}"), v));
+            }
+        }
+        Segment::Wildcard => match value {
+            Value::Array(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    out.push((format!("{pointer}/{i}"), v.clone()));
+                }
+            }
+            Value::Object(map) => {
+                for (k, v) in map.iter() {
+                    out.push((format!("{pointer}/{}", escape_pointer(k)), v.clone()));
+                }
+            }
+            _ => {}
+        },
+        Segment::RecursiveDescent => collect_descendants(pointer, value, out),
+        Segment::Slice(start, end, step) => {
+            if let Value::Array(items) = value {
+                for i in slice_indices(items.len(), *start, *end, *step) {
+                    out.push((format!("{pointer}/{i}"), items[i].clone()));
+                }
+            }
+        }
+        Segment::Filter(expr) => match value {
+            Value::Array(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    if matches_filter(expr, v) {
+                        out.push((format!("{pointer}/{i}"), v.clone()));
+                    }
+                }
+            }
+            Value::Object(map) => {
+                for (k, v) in map.iter() {
+                    if matches_filter(expr, v) {
+                        out.push((format!("{pointer}/{}", escape_pointer(k)), v.clone()));
+                    }
+                }
+            }
+            _ => {}
+        },
+    }
+}
+
+fn collect_descendants(pointer: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+    out.push((pointer.to_string(), value.clone()));
+    match value {
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                collect_descendants(&format!("{pointer}/{i}"), v, out);
+            }
+        }
+        Value::Object(map) => {
+            for (k, v) in map.iter() {
+                collect_descendants(&format!("{pointer}/{}", escape_pointer(k)), v, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn index_array(value: &Value, i: i64) -> Option<Value> {
+    let Value::Array(items) = value else {
+        return None;
+    };
+    let len = items.len() as i64;
+    let idx = if i < 0 { len + i } else { i };
+    if idx < 0 || idx >= len {
+        return None;
+    }
+    Some(items[idx as usize].clone())
+}
+
+fn normalize_index(value: &Value, i: i64) -> usize {
+    let Value::Array(items) = value else { return 0 };
+    let len = items.len() as i64;
+    (if i < 0 { len + i } else { i }) as usize
+}
+
+fn slice_indices(len: usize, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    if step == 0 || len == 0 {
+        return Vec::new();
+    }
+    let len_i = len as i64;
+    let clamp = |v: i64| -> i64 {
+        let v = if v < 0 { len_i + v } else { v };
+        v.clamp(0, len_i)
+    };
+
+    let mut indices = Vec::new();
+    if step > 0 {
+        let start = clamp(start.unwrap_or(0));
+        let end = clamp(end.unwrap_or(len_i));
+        let mut i = start;
+        while i < end {
+            indices.push(i as usize);
+            i += step;
+        }
+    } else {
+        let start = clamp(start.unwrap_or(len_i - 1)).min(len_i - 1);
+        let end = end.map(clamp).unwrap_or(-1);
+        let mut i = start;
+        while i > end {
+            if i >= 0 && i < len_i {
+                indices.push(i as usize);
+            }
+            i += step;
+        }
+    }
+    indices
+}
+
+fn matches_filter(expr: &FilterExpr, value: &Value) -> bool {
+    let Some(field_value) = value.pointer(&format!("/{}", expr.field.replace('.', "/"))) else {
+        return false;
+    };
+
+    match (&expr.value, field_value) {
+        (Literal::Number(expected), Value::Number(n)) => {
+            let Some(actual) = n.as_f64() else {
+                return false;
+            };
+            compare_f64(actual, *expected, expr.op)
+        }
+        (Literal::String(expected), Value::String(actual)) => compare_str(actual, expected, expr.op),
+        (Literal::Bool(expected), Value::Bool(actual)) => compare_eq(actual, expected, expr.op),
+        (Literal::Null, Value::Null) => matches!(expr.op, CompareOp::Eq),
+        _ => matches!(expr.op, CompareOp::Ne),
+    }
+}
+
+fn compare_f64(actual: f64, expected: f64, op: CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => actual == expected,
+        CompareOp::Ne => actual != expected,
+        CompareOp::Lt => actual < expected,
+        CompareOp::Le => actual <= expected,
+        CompareOp::Gt => actual > expected,
+        CompareOp::Ge => actual >= expected,
+    }
+}
+
+fn compare_str(actual: &str, expected: &str, op: CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => actual == expected,
+        CompareOp::Ne => actual != expected,
+        CompareOp::Lt => actual < expected,
+        CompareOp::Le => actual <= expected,
+        CompareOp::Gt => actual > expected,
+        CompareOp::Ge => actual >= expected,
+    }
+}
+
+fn compare_eq<T: PartialEq>(actual: &T, expected: &T, op: CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => actual == expected,
+        CompareOp::Ne => actual != expected,
+        _ => false,
+    }
+}
+
+/// Turns a raw JSON key into its JSON Pointer-escaped form (`~` -> `~0`, `/` -> `~1`).
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn parse(expression: &str) -> Result<Vec<Segment>, String> {
+    let expr = expression.trim().strip_prefix('$').unwrap_or(expression.trim());
+    let chars: Vec<char> = expr.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                if chars.get(i + 1) == Some(&'.') {
+                    segments.push(Segment::RecursiveDescent);
+                    // Consume only the first dot; the second one then starts
+                    // the child name (or is followed by `[`/`*`).
+                    i += 1;
+                } else {
+                    i += 1;
+                    let start = i;
+                    while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                        i += 1;
+                    }
+                    let name: String = chars[start..i].iter().collect();
+                    if name == "*" {
+                        segments.push(Segment::Wildcard);
+                    } else if !name.is_empty() {
+                        segments.push(Segment::Child(name));
+                    }
+                }
+            }
+            '[' => {
+                let close = find_matching_bracket(&chars, i)?;
+                let inner: String = chars[i + 1..close].iter().collect();
+                segments.push(parse_bracket(&inner)?);
+                i = close + 1;
+            }
+            _ => return Err(format!("unexpected character '{}' in JSONPath", chars[i])),
+        }
+    }
+
+    Ok(segments)
+}
+
+fn find_matching_bracket(chars: &[char], open: usize) -> Result<usize, String> {
+    let mut depth = 0;
+    for (offset, c) in chars[open..].iter().enumerate() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(open + offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err("unterminated '[' in JSONPath".to_string())
+}
+
+fn parse_bracket(inner: &str) -> Result<Segment, String> {
+    let inner = inner.trim();
+
+    if inner == "*" {
+        return Ok(Segment::Wildcard);
+    }
+
+    if let Some(filter) = inner.strip_prefix("?(").and_then(|s| s.strip_suffix(')')) {
+        return parse_filter(filter).map(Segment::Filter);
+    }
+
+    if let Some(quoted) = strip_quotes(inner) {
+        return Ok(Segment::Child(quoted));
+    }
+
+    if inner.contains(':') {
+        let parts: Vec<&str> = inner.split(':').collect();
+        let parse_opt = |s: &str| -> Result<Option<i64>, String> {
+            if s.is_empty() {
+                Ok(None)
+            } else {
+                s.parse::<i64>().map(Some).map_err(|_| format!("invalid slice bound '{s}'"))
+            }
+        };
+        let start = parse_opt(parts.first().copied().unwrap_or(""))?;
+        let end = parse_opt(parts.get(1).copied().unwrap_or(""))?;
+        let step = match parts.get(2).copied().unwrap_or("") {
+            "" => 1,
+            s => s.parse::<i64>().map_err(|_| format!("invalid slice step '{s}'"))?,
+        };
+        return Ok(Segment::Slice(start, end, step));
+    }
+
+    inner
+        .parse::<i64>()
+        .map(Segment::Index)
+        .map_err(|_| format!("invalid bracket expression '{inner}'"))
+}
+
+fn strip_quotes(s: &str) -> Option<String> {
+    if (s.starts_with('\'') && s.ends_with('\'') || s.starts_with('"') && s.ends_with('"'))
+        && s.len() >= 2
+    {
+        Some(s[1..s.len() - 1].to_string())
+    } else {
+        None
+    }
+}
+
+fn parse_filter(expr: &str) -> Result<FilterExpr, String> {
+    let expr = expr.trim();
+    const OPS: &[(&str, CompareOp)] = &[
+        ("<=", CompareOp::Le),
+        (">=", CompareOp::Ge),
+        ("==", CompareOp::Eq),
+        ("!=", CompareOp::Ne),
+        ("<", CompareOp::Lt),
+        (">", CompareOp::Gt),
+    ];
+
+    for (token, op) in OPS {
+        if let Some(idx) = expr.find(token) {
+            let field = expr[..idx].trim();
+            let value = expr[idx + token.len()..].trim();
+            let field = field
+                .strip_prefix("@.")
+                .ok_or_else(|| format!("filter field must start with '@.': '{field}'"))?;
+            return Ok(FilterExpr {
+                field: field.to_string(),
+                op: *op,
+                value: parse_literal(value)?,
+            });
+        }
+    }
+
+    Err(format!("unsupported filter expression '{expr}'"))
+}
+
+fn parse_literal(raw: &str) -> Result<Literal, String> {
+    if let Some(s) = strip_quotes(raw) {
+        return Ok(Literal::String(s));
+    }
+    match raw {
+        "true" => Ok(Literal::Bool(true)),
+        "false" => Ok(Literal::Bool(false)),
+        "null" => Ok(Literal::Null),
+        _ => raw
+            .parse::<f64>()
+            .map(Literal::Number)
+            .map_err(|_| format!("invalid filter literal '{raw}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pointers(root: &Value, expr: &str) -> Vec<String> {
+        query(root, expr).unwrap().into_iter().map(|m| m.pointer).collect()
+    }
+
+    #[test]
+    fn dot_child_access() {
+        let root = json!({"store": {"name": "corner"}});
+        assert_eq!(pointers(&root, "$.store.name"), vec!["/store/name"]);
+    }
+
+    #[test]
+    fn wildcard_over_array() {
+        let root = json!({"store": {"book": [{"author": "a"}, {"author": "b"}]}});
+        assert_eq!(
+            pointers(&root, "$.store.book[*].author"),
+            vec!["/store/book/0/author", "/store/book/1/author"]
+        );
+    }
+
+    #[test]
+    fn recursive_descent() {
+        let root = json!({"a": {"price": 1}, "b": {"price": 2}});
+        let matches = query(&root, "$..price").unwrap();
+        assert_eq!(matches.len(), 2);
+    }
+
+    #[test]
+    fn array_slice() {
+        let root = json!({"list": [0, 1, 2, 3, 4]});
+        assert_eq!(
+            pointers(&root, "$.list[1:3]"),
+            vec!["/list/1", "/list/2"]
+        );
+    }
+
+    #[test]
+    fn filter_expression() {
+        let root = json!({"book": [{"price": 8}, {"price": 22}]});
+        assert_eq!(pointers(&root, "$.book[?(@.price<10)]"), vec!["/book/0"]);
+    }
+}
@@ -1,6 +1,24 @@
+mod commands;
+mod indexing;
+mod jsonpath;
+mod protocol;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = protocol::register(tauri::Builder::default());
+
+    builder
+        .manage(indexing::DocStore::default())
+        .invoke_handler(tauri::generate_handler![
+            commands::load_file,
+            commands::get_subtree,
+            commands::query,
+            commands::open_file,
+            indexing::open_document,
+            indexing::get_rows,
+            indexing::set_expanded,
+            indexing::close_document,
+        ])
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -10,7 +28,8 @@ pub fn run() {
                 )?;
             }
 
-            // Load the file system and dialog plugins
+            // Load the file system and dialog plugins. No scope is granted here;
+            // `commands::open_file` adds exactly the path the user picks.
             app.handle().plugin(tauri_plugin_fs::init())?;
             app.handle().plugin(tauri_plugin_dialog::init())?;
             app.handle().plugin(tauri_plugin_process::init())?;
@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use tauri::http::{Request, Response};
+use tauri::Manager;
+
+use crate::commands;
+
+/// Scheme used to stream file bytes to the webview without going through IPC.
+pub const SCHEME: &str = "jsonlens";
+
+/// Handles `jsonlens://<path>` requests, serving the raw bytes of the file at
+/// `<path>` with HTTP `Range` support so the webview can `fetch()` just the
+/// window it needs instead of pulling the whole document over IPC.
+pub fn register<R: tauri::Runtime>(builder: tauri::Builder<R>) -> tauri::Builder<R> {
+    builder.register_uri_scheme_protocol(SCHEME, move |app, request| {
+        match handle_request(app, request) {
+            Ok(response) => response,
+            Err(status) => Response::builder()
+                .status(status)
+                .body(Vec::new())
+                .unwrap(),
+        }
+    })
+}
+
+fn handle_request<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    request: &Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, u16> {
+    let path = request_path(request).ok_or(400)?;
+    if !commands::is_allowed(app, Path::new(&path)) {
+        return Err(403);
+    }
+    let mut file = File::open(&path).map_err(|_| 404)?;
+    let len = file.metadata().map_err(|_| 500)?.len();
+
+    match request.headers().get("range").and_then(|v| v.to_str().ok()) {
+        Some(range) => {
+            let (start, end) = parse_range(range, len).ok_or(416)?;
+            let window = (end - start + 1) as usize;
+
+            file.seek(SeekFrom::Start(start)).map_err(|_| 500)?;
+            let mut buf = vec![0u8; window];
+            file.read_exact(&mut buf).map_err(|_| 500)?;
+
+            Response::builder()
+                .status(206)
+                .header("Content-Type", "application/json")
+                .header("Accept-Ranges", "bytes")
+                .header("Content-Range", format!("bytes {start}-{end}/{len}"))
+                .header("Content-Length", window.to_string())
+                .body(buf)
+                .map_err(|_| 500)
+        }
+        None => {
+            let mut buf = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut buf).map_err(|_| 500)?;
+
+            Response::builder()
+                .status(200)
+                .header("Content-Type", "application/json")
+                .header("Accept-Ranges", "bytes")
+                .header("Content-Length", buf.len().to_string())
+                .body(buf)
+                .map_err(|_| 500)
+        }
+    }
+}
+
+/// Extracts the filesystem path from a `jsonlens://<path>` request, undoing
+/// the percent-encoding the webview applies when building the URL.
+fn request_path(request: &Request<Vec<u8>>) -> Option<String> {
+    let uri = request.uri();
+    let raw = format!("{}{}", uri.host().unwrap_or_default(), uri.path());
+    percent_decode(&raw)
+}
+
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Parses a single-range `Range: bytes=start-end` header into an inclusive
+/// `(start, end)` byte pair. Suffix ranges (`bytes=-500`) and open-ended
+/// ranges (`bytes=500-`) are both supported; a suffix longer than the file is
+/// clamped to the whole file, while an explicit end at or past the file
+/// length is rejected (`None`).
+fn parse_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = header.strip_prefix("bytes=")?;
+    let (start, end) = spec.split_once('-')?;
+
+    if len == 0 {
+        return None;
+    }
+
+    let (start, end) = if start.is_empty() {
+        let suffix_len: u64 = end.parse().ok()?;
+        let suffix_len = suffix_len.min(len);
+        (len - suffix_len, len - 1)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() {
+            len - 1
+        } else {
+            end.parse().ok()?
+        };
+        (start, end)
+    };
+
+    if start > end || end >= len {
+        return None;
+    }
+    Some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bounded_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+    }
+
+    #[test]
+    fn parses_open_ended_range() {
+        assert_eq!(parse_range("bytes=900-", 1000), Some((900, 999)));
+    }
+
+    #[test]
+    fn parses_suffix_range() {
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_range() {
+        assert_eq!(parse_range("bytes=0-1000", 1000), None);
+    }
+
+    #[test]
+    fn decodes_percent_encoded_path() {
+        assert_eq!(
+            percent_decode("%2Ftmp%2Fmy%20file.json").as_deref(),
+            Some("/tmp/my file.json")
+        );
+    }
+}